@@ -0,0 +1,89 @@
+use crate::*;
+
+/// Storage backend used by [`ChunkStrategy`] to persist and retrieve chunk bytes.
+///
+/// Implementing this trait lets the upload node and the final-storage node
+/// differ, e.g. staging chunks in an S3-compatible object store instead of
+/// under `upload_dir` on local disk.
+///
+/// Same `async fn`-in-trait tradeoff as [`HandleStrategy`]: every call site
+/// here is generic over a concrete `B: ChunkBackend`, never a trait object,
+/// so there's no boxed-future cost to pay for object safety we don't need.
+#[allow(async_fn_in_trait)]
+pub trait ChunkBackend: Send + Sync {
+    /// Writes `data` to `path`, creating any parent directories it needs.
+    async fn put(&self, path: &str, data: &[u8]) -> std::io::Result<()>;
+
+    /// Reads the full contents stored at `path`.
+    async fn get(&self, path: &str) -> std::io::Result<Vec<u8>>;
+
+    /// Removes the object stored at `path`.
+    async fn delete(&self, path: &str) -> std::io::Result<()>;
+
+    /// Reports whether `path` currently exists.
+    async fn exists(&self, path: &str) -> bool;
+}
+
+/// [`ChunkBackend`] that reproduces the crate's original behavior: chunks are
+/// plain files under `upload_dir` on the local filesystem.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalFsBackend;
+
+impl ChunkBackend for LocalFsBackend {
+    async fn put(&self, path: &str, data: &[u8]) -> std::io::Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        async_write_to_file(path, data).await
+    }
+
+    async fn get(&self, path: &str) -> std::io::Result<Vec<u8>> {
+        async_read_from_file(path).await
+    }
+
+    async fn delete(&self, path: &str) -> std::io::Result<()> {
+        tokio::fs::remove_file(path).await
+    }
+
+    async fn exists(&self, path: &str) -> bool {
+        Path::new(path).exists()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_path(label: &str) -> String {
+        let nanos: u128 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("chunkify-backend-test-{}-{}/chunk.bin", label, nanos))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[tokio::test]
+    async fn put_creates_parent_dirs_and_get_round_trips() {
+        let path: String = unique_path("round-trip");
+        let backend: LocalFsBackend = LocalFsBackend;
+        assert!(!backend.exists(&path).await);
+        backend.put(&path, b"payload").await.unwrap();
+        assert!(backend.exists(&path).await);
+        assert_eq!(backend.get(&path).await.unwrap(), b"payload");
+    }
+
+    #[tokio::test]
+    async fn delete_removes_the_object() {
+        let path: String = unique_path("delete");
+        let backend: LocalFsBackend = LocalFsBackend;
+        backend.put(&path, b"payload").await.unwrap();
+        backend.delete(&path).await.unwrap();
+        assert!(!backend.exists(&path).await);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::*;
+use std::io::Read;
+
+/// Codec used to compress chunk bytes before they are written to storage.
+///
+/// The encoded form of a chunk is prefixed with a one-byte tag identifying the
+/// codec, so chunks written under different [`Compression`] settings can
+/// coexist on disk and still be decoded correctly during merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// One-byte tag identifying this codec in the encoded header.
+    fn tag(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Gzip => 1,
+            Self::Zstd => 2,
+        }
+    }
+
+    /// Recovers a [`Compression`] from its header tag.
+    fn from_tag(tag: u8) -> Result<Self, ChunkStrategyError> {
+        match tag {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Gzip),
+            2 => Ok(Self::Zstd),
+            other => Err(ChunkStrategyError::UnknownCodec(other)),
+        }
+    }
+
+    /// Compresses `data` and prepends a one-byte codec tag.
+    ///
+    /// # Arguments
+    ///
+    /// - `&[u8]` - Raw chunk bytes.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Vec<u8>, ChunkStrategyError>` - The tagged, encoded buffer.
+    pub fn encode(self, data: &[u8]) -> Result<Vec<u8>, ChunkStrategyError> {
+        let mut out: Vec<u8> = vec![self.tag()];
+        match self {
+            Self::None => out.extend_from_slice(data),
+            Self::Gzip => {
+                use flate2::write::GzEncoder;
+                use flate2::Compression as GzLevel;
+                let mut encoder: GzEncoder<Vec<u8>> = GzEncoder::new(Vec::new(), GzLevel::default());
+                encoder
+                    .write_all(data)
+                    .map_err(|e| ChunkStrategyError::Compress(e.to_string()))?;
+                out.extend(
+                    encoder
+                        .finish()
+                        .map_err(|e| ChunkStrategyError::Compress(e.to_string()))?,
+                );
+            }
+            Self::Zstd => {
+                let compressed: Vec<u8> = zstd::stream::encode_all(data, 0)
+                    .map_err(|e| ChunkStrategyError::Compress(e.to_string()))?;
+                out.extend(compressed);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decodes a header-tagged buffer produced by [`Compression::encode`].
+    ///
+    /// # Arguments
+    ///
+    /// - `&[u8]` - Tagged, encoded buffer.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Vec<u8>, ChunkStrategyError>` - The original raw bytes.
+    pub fn decode(encoded: &[u8]) -> Result<Vec<u8>, ChunkStrategyError> {
+        let (&tag, body) = encoded
+            .split_first()
+            .ok_or(ChunkStrategyError::UnknownCodec(0))?;
+        match Self::from_tag(tag)? {
+            Self::None => Ok(body.to_vec()),
+            Self::Gzip => {
+                use flate2::read::GzDecoder;
+                let mut decoder: GzDecoder<&[u8]> = GzDecoder::new(body);
+                let mut out: Vec<u8> = Vec::new();
+                decoder
+                    .read_to_end(&mut out)
+                    .map_err(|e| ChunkStrategyError::Compress(e.to_string()))?;
+                Ok(out)
+            }
+            Self::Zstd => zstd::stream::decode_all(body)
+                .map_err(|e| ChunkStrategyError::Compress(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_gzip_and_zstd_round_trip() {
+        let data: &[u8] = b"the quick brown fox jumps over the lazy dog, repeated for compressibility the quick brown fox jumps over the lazy dog";
+        for codec in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let encoded: Vec<u8> = codec.encode(data).unwrap();
+            let decoded: Vec<u8> = Compression::decode(&encoded).unwrap();
+            assert_eq!(decoded, data, "round trip failed for {:?}", codec);
+        }
+    }
+
+    #[test]
+    fn decode_rejects_unknown_codec_tag() {
+        let encoded: Vec<u8> = vec![99, 1, 2, 3];
+        let result: Result<Vec<u8>, ChunkStrategyError> = Compression::decode(&encoded);
+        assert!(matches!(result, Err(ChunkStrategyError::UnknownCodec(99))));
+    }
+}
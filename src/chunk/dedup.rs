@@ -0,0 +1,30 @@
+use crate::*;
+
+/// A chunk's canonical on-disk location and how many chunk indices reference it.
+pub struct DedupEntry {
+    /// Path of the single stored copy of this content.
+    pub canonical_path: String,
+    /// Number of chunk indices (across one or more uploads) pointing at this copy.
+    pub refcount: usize,
+    /// On-disk (possibly compressed) size of the stored copy.
+    pub compressed_size: usize,
+}
+
+/// Content-addressed store mapping a chunk's digest to its single stored copy.
+///
+/// Looked up by [`HandleStrategy::save_chunk`] before writing: a hit skips the
+/// write and just bumps the refcount, so identical content is only stored once.
+pub static DEDUP_STORE: Lazy<DashMap<ChunkDigest, DedupEntry>> = Lazy::new(DashMap::new);
+
+/// Space-efficiency statistics for an upload session.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UploadStatistics {
+    /// Total number of chunks saved so far.
+    pub count: usize,
+    /// Total bytes across all chunks, including deduplicated ones.
+    pub size: usize,
+    /// Number of chunks whose content already existed in the dedup store.
+    pub deduplicated_count: usize,
+    /// Bytes not written to disk because their content was already stored.
+    pub saved_bytes: usize,
+}
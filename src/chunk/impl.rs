@@ -4,8 +4,8 @@ use crate::*;
 impl<'a, F> ChunkNaming<'a> for F where F: Fn(&'a str, usize) -> String + Send + Sync {}
 
 /// Implementation of chunk strategy methods.
-impl<'a> ChunkStrategy<'a> {
-    /// Creates a new chunk strategy instance.
+impl<'a> ChunkStrategy<'a, LocalFsBackend> {
+    /// Creates a new chunk strategy instance backed by the local filesystem.
     ///
     /// # Arguments
     ///
@@ -27,6 +27,46 @@ impl<'a> ChunkStrategy<'a> {
         total_chunks: usize,
         file_name_func: F,
     ) -> NewChunkStrategyResult<'a>
+    where
+        F: ChunkNaming<'a> + 'static,
+    {
+        Self::new_with_backend(
+            start_chunk_index,
+            upload_dir,
+            file_id,
+            file_name,
+            total_chunks,
+            file_name_func,
+            LocalFsBackend,
+        )
+    }
+}
+
+impl<'a, B: ChunkBackend> ChunkStrategy<'a, B> {
+    /// Creates a new chunk strategy instance backed by a custom [`ChunkBackend`].
+    ///
+    /// # Arguments
+    ///
+    /// - `usize` - Starting chunk index (0-based)
+    /// - `&str` - Directory path for chunk storage
+    /// - `&str` - Unique file identifier
+    /// - `&str` - Original filename
+    /// - `usize` - Total chunks count
+    /// - `F` - Function implementing ChunkNaming trait
+    /// - `B` - Storage backend used to persist chunk bytes
+    ///
+    /// # Returns
+    ///
+    /// - `NewChunkStrategyResult<B>` - Result containing strategy or error
+    pub fn new_with_backend<F>(
+        start_chunk_index: usize,
+        upload_dir: &'a str,
+        file_id: &'a str,
+        file_name: &'a str,
+        total_chunks: usize,
+        file_name_func: F,
+        backend: B,
+    ) -> NewChunkStrategyResult<'a, B>
     where
         F: ChunkNaming<'a> + 'static,
     {
@@ -43,9 +83,64 @@ impl<'a> ChunkStrategy<'a> {
             file_name,
             total_chunks,
             file_name_func: Box::new(file_name_func),
+            backend,
+            compression: Compression::None,
+            max_bytes: None,
         })
     }
 
+    /// Rebuilds this strategy with a different storage backend.
+    ///
+    /// # Arguments
+    ///
+    /// - `B2` - The replacement storage backend.
+    ///
+    /// # Returns
+    ///
+    /// - `ChunkStrategy<'a, B2>` - The strategy using the new backend.
+    pub fn with_backend<B2: ChunkBackend>(self, backend: B2) -> ChunkStrategy<'a, B2> {
+        ChunkStrategy {
+            upload_dir: self.upload_dir,
+            start_chunk_index: self.start_chunk_index,
+            file_id: self.file_id,
+            file_name: self.file_name,
+            total_chunks: self.total_chunks,
+            file_name_func: self.file_name_func,
+            backend,
+            compression: self.compression,
+            max_bytes: self.max_bytes,
+        }
+    }
+
+    /// Sets the codec used to compress chunk bytes before they are written.
+    ///
+    /// # Arguments
+    ///
+    /// - `Compression` - The codec to use.
+    ///
+    /// # Returns
+    ///
+    /// - `Self` - The strategy with the new compression setting.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets a ceiling on the process-wide [`USED_SPACE`]: a write that would push
+    /// it past this many bytes fails with [`ChunkStrategyError::QuotaExceeded`].
+    ///
+    /// # Arguments
+    ///
+    /// - `u64` - Maximum bytes allowed.
+    ///
+    /// # Returns
+    ///
+    /// - `Self` - The strategy with the new quota.
+    pub fn with_quota(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
     /// Gets the JSON path for a chunk file.
     ///
     /// # Arguments
@@ -62,6 +157,14 @@ impl<'a> ChunkStrategy<'a> {
 
     /// Gets the full path for a chunk file.
     ///
+    /// Rejects names produced by `file_name_func` that exceed
+    /// [`MAX_CHUNK_FILE_NAME_LEN`], to stay safe across filesystems.
+    ///
+    /// Chunk data lives under `<upload_dir>/chunks/`, alongside but distinct
+    /// from the `cdc/` segment directory and the top-level sidecar/output
+    /// files, so [`init_used_space`] can recognize which files on disk are
+    /// actually quota-tracked chunk data without guessing from file names.
+    ///
     /// # Arguments
     ///
     /// - `&str` - File identifier
@@ -69,15 +172,132 @@ impl<'a> ChunkStrategy<'a> {
     ///
     /// # Returns
     ///
-    /// - `String` - Absolute path to chunk file
-    fn get_chunk_path(&self, file_id: &'a str, chunk_index: usize) -> String {
+    /// - `Result<String, ChunkStrategyError>` - Path to chunk file under `<upload_dir>/chunks/`
+    fn get_chunk_path(&self, file_id: &'a str, chunk_index: usize) -> Result<String, ChunkStrategyError> {
+        let name: String = self.get_chunk_json_path(file_id, chunk_index);
+        if name.len() > MAX_CHUNK_FILE_NAME_LEN {
+            return Err(ChunkStrategyError::FileNameTooLong(name.len()));
+        }
+        Ok(Path::new(&self.upload_dir)
+            .join("chunks")
+            .join(name)
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// Gets the path of this upload's durable resume sidecar.
+    ///
+    /// # Returns
+    ///
+    /// - `String` - Path to the sidecar file recording received-chunk state.
+    fn sidecar_path(&self) -> String {
         Path::new(&self.upload_dir)
-            .join(self.get_chunk_json_path(file_id, chunk_index))
+            .join(format!("{}.resume", self.file_id))
             .to_string_lossy()
             .into_owned()
     }
 
-    /// Saves a chunk to the specified path.
+    /// Durably writes `status`'s received-chunk bitmap to the resume sidecar,
+    /// fsyncing it so a crash right after this call still leaves a readable index.
+    ///
+    /// # Arguments
+    ///
+    /// - `&UploadStatus` - Status to persist.
+    ///
+    /// # Returns
+    ///
+    /// - `ChunkStrategyResult` - Result of the write.
+    async fn write_sidecar(&self, status: &UploadStatus) -> ChunkStrategyResult {
+        let sidecar_path: String = self.sidecar_path();
+        let bitmap: String = status
+            .received
+            .iter()
+            .map(|&received| if received { '1' } else { '0' })
+            .collect();
+        let contents: String = format!("{}\n{}\n", self.total_chunks, bitmap);
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut file: File = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&sidecar_path)?;
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .await
+        .map_err(|e| ChunkStrategyError::WriteChunk(e.to_string()))?
+        .map_err(|e| ChunkStrategyError::WriteChunk(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Rebuilds this upload's in-memory status from its durable sidecar,
+    /// verifying on disk which recorded chunks are still actually present.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ResumeState, ChunkStrategyError>` - The reconciled resume state.
+    pub async fn resume(&self) -> Result<ResumeState, ChunkStrategyError> {
+        let sidecar_path: String = self.sidecar_path();
+        let raw: Vec<u8> = match self.backend.get(&sidecar_path).await {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                return Ok(ResumeState {
+                    total_chunks: self.total_chunks,
+                    received: vec![false; self.total_chunks],
+                });
+            }
+        };
+        let text: std::borrow::Cow<'_, str> = String::from_utf8_lossy(&raw);
+        let mut lines = text.lines();
+        let total_chunks: usize = lines
+            .next()
+            .and_then(|line| line.parse().ok())
+            .unwrap_or(self.total_chunks);
+        let bitmap_line: &str = lines.next().unwrap_or("");
+        let mut received: Vec<bool> = vec![false; total_chunks];
+        for (i, flag) in bitmap_line.chars().enumerate().take(total_chunks) {
+            received[i] = flag == '1';
+        }
+        let mut digests: Vec<Option<ChunkDigest>> = vec![None; total_chunks];
+        for (i, received) in received.iter_mut().enumerate() {
+            if !*received {
+                continue;
+            }
+            let chunk_path: String = self.get_chunk_path(self.file_id, i)?;
+            // Recompute the digest from the surviving bytes rather than leaving
+            // it `None`: merge_chunks treats a `None` digest as "never had one",
+            // which skips its checksum check and, worse, routes its deletion
+            // around the DEDUP_STORE refcount instead of through it, leaking
+            // the canonical copy's bookkeeping for any chunk this one deduped
+            // against.
+            let recovered: Option<ChunkDigest> = match self.backend.get(&chunk_path).await {
+                Ok(encoded) => Compression::decode(&encoded)
+                    .ok()
+                    .map(|data| sha256_digest(&data)),
+                Err(_) => None,
+            };
+            match recovered {
+                Some(digest) => digests[i] = Some(digest),
+                None => *received = false,
+            }
+        }
+        let status: UploadStatus = UploadStatus {
+            received: received.clone(),
+            digests,
+            deduplicated_count: 0,
+            saved_bytes: 0,
+            total_bytes: 0,
+            raw_sizes: vec![0; total_chunks],
+            compressed_sizes: vec![0; total_chunks],
+        };
+        UPLOADING_FILES.insert(self.file_id.to_owned(), RwLock::new(status));
+        Ok(ResumeState {
+            total_chunks,
+            received,
+        })
+    }
+
+    /// Saves a chunk to the specified path through the backend.
     ///
     /// # Arguments
     ///
@@ -88,51 +308,219 @@ impl<'a> ChunkStrategy<'a> {
     ///
     /// - `ChunkStrategyResult` - Result of save operation.
     async fn save_chunk(&self, chunk_path: &str, chunk_data: &[u8]) -> ChunkStrategyResult {
-        async_write_to_file(chunk_path, chunk_data)
-            .await
-            .map_err(|e| {
-                ChunkStrategyError::WriteChunk(format!(
-                    "Failed to write chunk to {}: {}",
-                    chunk_path, e
+        self.backend.put(chunk_path, chunk_data).await.map_err(|e| {
+            ChunkStrategyError::WriteChunk(format!(
+                "Failed to write chunk to {}: {}",
+                chunk_path, e
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Compresses `data`, reserves its encoded size against the quota, and
+    /// writes it to `path`, rolling back the reservation on write failure.
+    ///
+    /// Shared by [`HandleStrategy::save_chunk`] and [`Self::rechunk_and_store`]
+    /// so CDC re-chunked segments are bound by the same quota, compression,
+    /// and [`USED_SPACE`] accounting as ordinary chunk uploads, rather than
+    /// bypassing them.
+    ///
+    /// # Arguments
+    ///
+    /// - `&str` - Path to write the encoded data to.
+    /// - `&[u8]` - Raw data to encode and store.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<usize, ChunkStrategyError>` - The encoded (on-disk) size.
+    async fn encode_and_store(&self, path: &str, data: &[u8]) -> Result<usize, ChunkStrategyError> {
+        let encoded: Vec<u8> = self.compression.encode(data)?;
+        let encoded_len: usize = encoded.len();
+        match self.max_bytes {
+            Some(max_bytes) if !try_reserve(encoded_len as u64, max_bytes) => {
+                return Err(ChunkStrategyError::QuotaExceeded);
+            }
+            Some(_) => {}
+            None => {
+                USED_SPACE.fetch_add(encoded_len as u64, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+        if let Err(e) = self.save_chunk(path, &encoded).await {
+            USED_SPACE.fetch_sub(encoded_len as u64, std::sync::atomic::Ordering::SeqCst);
+            return Err(e);
+        }
+        Ok(encoded_len)
+    }
+
+    /// Reports how many chunks have been saved so far and how much dedup saved.
+    ///
+    /// # Returns
+    ///
+    /// - `UploadStatistics` - Snapshot of this upload's progress and savings.
+    pub async fn statistics(&self) -> UploadStatistics {
+        match UPLOADING_FILES.get(self.file_id) {
+            Some(status) => {
+                let status: tokio::sync::RwLockReadGuard<'_, UploadStatus> = status.read().await;
+                UploadStatistics {
+                    count: status.received.iter().filter(|&&r| r).count(),
+                    size: status.total_bytes,
+                    deduplicated_count: status.deduplicated_count,
+                    saved_bytes: status.saved_bytes,
+                }
+            }
+            None => UploadStatistics::default(),
+        }
+    }
+
+    /// Re-chunks the assembled upload on content-defined boundaries and stores each
+    /// resulting segment under `<upload_dir>/cdc/<content-key>`, skipping segments
+    /// that are already present so identical content across uploads is written once.
+    /// Each newly stored segment goes through [`Self::encode_and_store`], so CDC
+    /// output is bound by the same quota and compression as ordinary chunks.
+    ///
+    /// # Arguments
+    ///
+    /// - `&CdcConfig` - Minimum, average, and maximum segment sizes.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Vec<String>, ChunkStrategyError>` - The ordered list of segment
+    ///   content keys making up the file.
+    pub async fn rechunk_and_store(
+        &self,
+        config: &CdcConfig,
+    ) -> Result<Vec<String>, ChunkStrategyError> {
+        let mut assembled: Vec<u8> = Vec::new();
+        for i in self.start_chunk_index..self.total_chunks {
+            let chunk_path: String = self.get_chunk_path(self.file_id, i)?;
+            let digest: Option<ChunkDigest> = match UPLOADING_FILES.get(self.file_id) {
+                Some(status) => status.read().await.digests.get(i).copied().flatten(),
+                None => None,
+            };
+            let on_disk: bool = self.backend.exists(&chunk_path).await;
+            let read_path: String = match digest {
+                Some(digest) if !on_disk => DEDUP_STORE
+                    .get(&digest)
+                    .map_or(chunk_path, |entry| entry.canonical_path.clone()),
+                _ => chunk_path,
+            };
+            let encoded: Vec<u8> = self.backend.get(&read_path).await.map_err(|e| {
+                ChunkStrategyError::ReadChunk(format!(
+                    "Failed to read chunk from {}: {}",
+                    read_path, e
                 ))
             })?;
-        Ok(())
+            let chunk_data: Vec<u8> = Compression::decode(&encoded)?;
+            assembled.extend_from_slice(&chunk_data);
+        }
+        let cdc_dir: String = Path::new(&self.upload_dir)
+            .join("cdc")
+            .to_string_lossy()
+            .into_owned();
+        let mut keys: Vec<String> = Vec::new();
+        for segment in fast_cdc_split(&assembled, config) {
+            let key: String = segment_content_key(segment);
+            let segment_path: String = Path::new(&cdc_dir)
+                .join(&key)
+                .to_string_lossy()
+                .into_owned();
+            if !self.backend.exists(&segment_path).await {
+                self.encode_and_store(&segment_path, segment).await?;
+            }
+            keys.push(key);
+        }
+        Ok(keys)
     }
 }
 
 /// Implementation of handle strategy for chunk operations.
-impl<'a> HandleStrategy<'a> for ChunkStrategy<'a> {
-    /// Saves a chunk with index validation.
+impl<'a, B: ChunkBackend> HandleStrategy<'a> for ChunkStrategy<'a, B> {
+    /// Saves a chunk with index validation and optional checksum verification.
     ///
     /// # Arguments
     ///
     /// - `&'a [u8]` - Chunk data.
     /// - `usize` - Chunk index.
+    /// - `Option<ChunkDigest>` - Expected SHA-256 digest of the chunk, if known.
     ///
     /// # Returns
     ///
     /// - `ChunkStrategyResult` - Result of save operation.
-    async fn save_chunk(&self, chunk_data: &'a [u8], chunk_index: usize) -> ChunkStrategyResult {
-        if !Path::new(&self.upload_dir).exists() {
-            fs::create_dir_all(&self.upload_dir)
-                .map_err(|e| ChunkStrategyError::CreateDirectory(e.to_string()))?;
-        }
-        let chunk_path: String = self.get_chunk_path(self.file_id, chunk_index);
-        self.save_chunk(&chunk_path, &chunk_data).await?;
-        let chunks_status: RefMut<'_, String, RwLock<Vec<bool>>> = UPLOADING_FILES
-            .entry(self.file_id.to_owned())
-            .or_insert_with(|| RwLock::new(vec![false; self.total_chunks]));
-        let mut chunks_status: RwLockWriteGuard<'_, Vec<bool>> = chunks_status.write().await;
-        if chunks_status.len() != self.total_chunks {
-            *chunks_status = vec![false; self.total_chunks];
+    async fn save_chunk(
+        &self,
+        chunk_data: &'a [u8],
+        chunk_index: usize,
+        expected: Option<ChunkDigest>,
+    ) -> ChunkStrategyResult {
+        let content_hash: ChunkDigest = sha256_digest(chunk_data);
+        if let Some(expected) = expected {
+            if content_hash != expected {
+                return Err(ChunkStrategyError::ChecksumMismatch(chunk_index));
+            }
         }
-        if chunk_index >= chunks_status.len() {
-            return Err(ChunkStrategyError::IndexOutOfBounds(
-                chunk_index,
-                self.total_chunks,
-            ));
+
+        let already_received: bool = {
+            let entry: RefMut<'_, String, RwLock<UploadStatus>> = UPLOADING_FILES
+                .entry(self.file_id.to_owned())
+                .or_insert_with(|| RwLock::new(UploadStatus::new(self.total_chunks)));
+            let mut guard: RwLockWriteGuard<'_, UploadStatus> = entry.write().await;
+            if guard.received.len() != self.total_chunks {
+                *guard = UploadStatus::new(self.total_chunks);
+            }
+            if chunk_index >= guard.received.len() {
+                return Err(ChunkStrategyError::IndexOutOfBounds(
+                    chunk_index,
+                    self.total_chunks,
+                ));
+            }
+            guard.received[chunk_index]
+            // `entry` and `guard` both drop here, before any `.await` below.
+        };
+        if already_received {
+            // A retried save for a chunk index that already landed (ordinary
+            // under resumable uploads): skip it outright rather than bumping
+            // DEDUP_STORE's refcount again, which merge_chunks would only ever
+            // release once for this index, leaking the canonical copy.
+            return Ok(());
         }
-        chunks_status[chunk_index] = true;
+
+        let chunk_path: String = self.get_chunk_path(self.file_id, chunk_index)?;
+        let (deduplicated, compressed_size): (bool, usize) =
+            if let Some(mut entry) = DEDUP_STORE.get_mut(&content_hash) {
+                entry.refcount += 1;
+                (true, entry.compressed_size)
+            } else {
+                let encoded_len: usize = self.encode_and_store(&chunk_path, chunk_data).await?;
+                DEDUP_STORE.insert(
+                    content_hash,
+                    DedupEntry {
+                        canonical_path: chunk_path,
+                        refcount: 1,
+                        compressed_size: encoded_len,
+                    },
+                );
+                (false, encoded_len)
+            };
+        let status_snapshot: UploadStatus = {
+            let entry: RefMut<'_, String, RwLock<UploadStatus>> = UPLOADING_FILES
+                .entry(self.file_id.to_owned())
+                .or_insert_with(|| RwLock::new(UploadStatus::new(self.total_chunks)));
+            let mut guard: RwLockWriteGuard<'_, UploadStatus> = entry.write().await;
+            guard.received[chunk_index] = true;
+            guard.digests[chunk_index] = Some(content_hash);
+            guard.total_bytes += chunk_data.len();
+            guard.raw_sizes[chunk_index] = chunk_data.len();
+            guard.compressed_sizes[chunk_index] = compressed_size;
+            if deduplicated {
+                guard.deduplicated_count += 1;
+                guard.saved_bytes += chunk_data.len();
+            }
+            guard.clone()
+            // `entry` (the DashMap shard guard) and `guard` both drop here, before
+            // the `.await` below, so concurrent saves for other file_ids (or the
+            // same one) never block on this shard while we're awaiting I/O.
+        };
+        self.write_sidecar(&status_snapshot).await?;
         Ok(())
     }
 
@@ -142,15 +530,17 @@ impl<'a> HandleStrategy<'a> for ChunkStrategy<'a> {
     ///
     /// - `ChunkStrategyResult` - Result of merge operation.
     async fn merge_chunks(&self) -> ChunkStrategyResult {
-        let chunks_status: RefMut<'_, String, RwLock<Vec<bool>>> = UPLOADING_FILES
+        let chunks_status: RefMut<'_, String, RwLock<UploadStatus>> = UPLOADING_FILES
             .entry(self.file_id.to_owned())
-            .or_insert_with(|| RwLock::new(vec![false; self.total_chunks]));
-        let mut chunks_status: RwLockWriteGuard<'_, Vec<bool>> = chunks_status.write().await;
-        let all_chunks_uploaded: bool = chunks_status.iter().all(|&status| status);
+            .or_insert_with(|| RwLock::new(UploadStatus::new(self.total_chunks)));
+        let mut chunks_status: RwLockWriteGuard<'_, UploadStatus> = chunks_status.write().await;
+        let all_chunks_uploaded: bool = chunks_status.received.iter().all(|&status| status);
         if !all_chunks_uploaded {
             return Err(ChunkStrategyError::Merge);
         }
-        chunks_status.clear();
+        let digests: Vec<Option<ChunkDigest>> = chunks_status.digests.clone();
+        chunks_status.received.clear();
+        chunks_status.digests.clear();
         drop(chunks_status);
         let final_path: String = Path::new(&self.upload_dir)
             .join(self.file_name)
@@ -163,18 +553,206 @@ impl<'a> HandleStrategy<'a> for ChunkStrategy<'a> {
             .map_err(|e| ChunkStrategyError::CreateOutputFile(e.to_string()))?;
         let mut writer: BufWriter<File> = BufWriter::new(output_file);
         for i in self.start_chunk_index..self.total_chunks {
-            let chunk_path: String = self.get_chunk_path(self.file_id, i);
-            let chunk_data: Vec<u8> = async_read_from_file(&chunk_path).await.map_err(|e| {
+            let chunk_path: String = self.get_chunk_path(self.file_id, i)?;
+            let digest: Option<ChunkDigest> = digests.get(i).copied().flatten();
+            let read_path: String = if self.backend.exists(&chunk_path).await {
+                chunk_path.clone()
+            } else if let Some(digest) = digest {
+                DEDUP_STORE
+                    .get(&digest)
+                    .map(|entry| entry.canonical_path.clone())
+                    .ok_or_else(|| {
+                        ChunkStrategyError::ReadChunk(format!(
+                            "Deduplicated chunk {} is missing its canonical copy",
+                            i
+                        ))
+                    })?
+            } else {
+                chunk_path.clone()
+            };
+            let encoded: Vec<u8> = self.backend.get(&read_path).await.map_err(|e| {
                 ChunkStrategyError::ReadChunk(format!(
                     "Failed to read chunk from {}: {}",
-                    chunk_path, e
+                    read_path, e
                 ))
             })?;
+            let chunk_data: Vec<u8> = Compression::decode(&encoded)?;
+            if let Some(expected) = digest {
+                if sha256_digest(&chunk_data) != expected {
+                    return Err(ChunkStrategyError::ChecksumMismatch(i));
+                }
+            }
             writer
                 .write_all(&chunk_data)
                 .map_err(|e| ChunkStrategyError::WriteOutput(e.to_string()))?;
-            let _ = fs::remove_file(&chunk_path);
+            if let Some(digest) = digest {
+                let mut freed_bytes: u64 = 0;
+                let should_remove: bool = DEDUP_STORE
+                    .get_mut(&digest)
+                    .map(|mut entry| {
+                        entry.refcount = entry.refcount.saturating_sub(1);
+                        if entry.refcount == 0 {
+                            freed_bytes = entry.compressed_size as u64;
+                            true
+                        } else {
+                            false
+                        }
+                    })
+                    .unwrap_or(false);
+                if should_remove {
+                    DEDUP_STORE.remove(&digest);
+                    let _ = self.backend.delete(&read_path).await;
+                    USED_SPACE.fetch_sub(freed_bytes, std::sync::atomic::Ordering::SeqCst);
+                }
+            } else {
+                let _ = self.backend.delete(&chunk_path).await;
+            }
         }
+        let _ = self.backend.delete(&self.sidecar_path()).await;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Creates a fresh, unused temp directory for a test and returns its path.
+    fn unique_dir(label: &str) -> String {
+        let nanos: u128 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir: std::path::PathBuf =
+            std::env::temp_dir().join(format!("chunkify-test-{}-{}", label, nanos));
+        fs::create_dir_all(&dir).unwrap();
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn chunk_name(file_id: &str, index: usize) -> String {
+        format!("{}.part{}", file_id, index)
+    }
+
+    #[tokio::test]
+    async fn save_chunk_rejects_checksum_mismatch() {
+        let dir: String = unique_dir("checksum-mismatch");
+        let strategy: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, "file-a", "out.bin", 1, chunk_name).unwrap();
+        let bogus_digest: ChunkDigest = [0u8; 32];
+        let result: ChunkStrategyResult = strategy
+            .save_chunk(b"checksum-mismatch-payload", 0, Some(bogus_digest))
+            .await;
+        assert!(matches!(
+            result,
+            Err(ChunkStrategyError::ChecksumMismatch(0))
+        ));
+    }
+
+    #[tokio::test]
+    async fn save_chunk_accepts_correct_checksum() {
+        let dir: String = unique_dir("checksum-match");
+        let strategy: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, "file-b", "out.bin", 1, chunk_name).unwrap();
+        let data: &[u8] = b"checksum-match-payload";
+        let digest: ChunkDigest = sha256_digest(data);
+        let result: ChunkStrategyResult = strategy.save_chunk(data, 0, Some(digest)).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn save_chunk_dedupes_identical_content_across_uploads() {
+        let dir: String = unique_dir("dedup");
+        let data: &[u8] = b"dedup-shared-payload-chunk0-4";
+        let strategy_a: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, "dedup-file-a", "a.bin", 1, chunk_name).unwrap();
+        strategy_a.save_chunk(data, 0, None).await.unwrap();
+
+        let strategy_b: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, "dedup-file-b", "b.bin", 1, chunk_name).unwrap();
+        strategy_b.save_chunk(data, 0, None).await.unwrap();
+
+        let stats_b: UploadStatistics = strategy_b.statistics().await;
+        assert_eq!(stats_b.deduplicated_count, 1);
+        assert_eq!(stats_b.saved_bytes, data.len());
+    }
+
+    #[tokio::test]
+    async fn save_chunk_retry_for_an_already_received_index_does_not_rebump_refcount() {
+        let dir: String = unique_dir("retry-no-rebump");
+        let data: &[u8] = b"retry-no-rebump-owned-payload-chunk0-4";
+        let file_id: &str = "retry-no-rebump-owner";
+        let strategy: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, file_id, "out.bin", 1, chunk_name).unwrap();
+        strategy.save_chunk(data, 0, None).await.unwrap();
+
+        let digest: ChunkDigest = sha256_digest(data);
+        assert_eq!(DEDUP_STORE.get(&digest).unwrap().refcount, 1);
+
+        // A client retrying a save it thinks may not have landed must not bump
+        // the refcount again: merge_chunks only ever releases one reference per
+        // chunk index, so a second bump here would leak the canonical copy.
+        strategy.save_chunk(data, 0, None).await.unwrap();
+        assert_eq!(DEDUP_STORE.get(&digest).unwrap().refcount, 1);
+
+        strategy.merge_chunks().await.unwrap();
+        assert!(DEDUP_STORE.get(&digest).is_none());
+    }
+
+    #[tokio::test]
+    async fn save_chunk_enforces_quota_ceiling() {
+        let dir: String = unique_dir("quota");
+        let data: &[u8] = b"quota-test-payload-chunk0-6";
+        let ceiling: u64 = used_space() + (data.len() as u64);
+        let strategy: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, "quota-file", "out.bin", 1, chunk_name)
+                .unwrap()
+                .with_quota(ceiling);
+        let result: ChunkStrategyResult = strategy.save_chunk(data, 0, None).await;
+        assert!(matches!(result, Err(ChunkStrategyError::QuotaExceeded)));
+    }
+
+    #[tokio::test]
+    async fn resume_reconstructs_state_from_sidecar_after_crash() {
+        let dir: String = unique_dir("resume");
+        let data0: &[u8] = b"resume-chunk-zero-chunk0-7";
+        let data1: &[u8] = b"resume-chunk-one-chunk0-7";
+        let file_id: &str = "resume-file";
+        let strategy: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, file_id, "out.bin", 2, chunk_name).unwrap();
+        strategy.save_chunk(data0, 0, None).await.unwrap();
+
+        // Simulate a crash: the in-memory status is gone, only the sidecar remains.
+        UPLOADING_FILES.remove(file_id);
+
+        let resumed: ResumeState = strategy.resume().await.unwrap();
+        assert_eq!(resumed.total_chunks, 2);
+        assert_eq!(resumed.received, vec![true, false]);
+
+        strategy.save_chunk(data1, 1, None).await.unwrap();
+        strategy.merge_chunks().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn resume_restores_digest_so_merge_releases_dedup_refcount() {
+        let dir: String = unique_dir("resume-dedup");
+        let data: &[u8] = b"resume-dedup-owned-payload-chunk0-7";
+        let file_id: &str = "resume-dedup-owner";
+        let strategy: ChunkStrategy<'_, LocalFsBackend> =
+            ChunkStrategy::new(0, &dir, file_id, "out.bin", 1, chunk_name).unwrap();
+        strategy.save_chunk(data, 0, None).await.unwrap();
+
+        let digest: ChunkDigest = sha256_digest(data);
+        assert_eq!(DEDUP_STORE.get(&digest).unwrap().refcount, 1);
+
+        // Simulate a crash: the in-memory status, and its recorded digest, is gone.
+        UPLOADING_FILES.remove(file_id);
+        strategy.resume().await.unwrap();
+        strategy.merge_chunks().await.unwrap();
+
+        // A recovered digest routes the merge through the DEDUP_STORE refcount
+        // branch, releasing the entry instead of leaking it pointed at a file
+        // that was just deleted out from under it.
+        assert!(DEDUP_STORE.get(&digest).is_none());
+    }
+}
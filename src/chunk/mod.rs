@@ -0,0 +1,169 @@
+use crate::*;
+
+mod backend;
+mod cdc;
+mod compression;
+mod dedup;
+mod r#impl;
+mod quota;
+mod resume;
+
+pub use backend::*;
+pub use cdc::*;
+pub use compression::*;
+pub use dedup::*;
+pub use quota::*;
+pub use resume::*;
+
+/// SHA-256 digest of a chunk's bytes.
+pub type ChunkDigest = [u8; 32];
+
+/// Computes the SHA-256 digest of a chunk's bytes.
+///
+/// # Arguments
+///
+/// - `&[u8]` - Chunk data.
+///
+/// # Returns
+///
+/// - `ChunkDigest` - The resulting digest.
+pub fn sha256_digest(data: &[u8]) -> ChunkDigest {
+    use sha2::{Digest, Sha256};
+    let mut hasher: Sha256 = Sha256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Per-chunk arrival state for an in-flight upload.
+#[derive(Debug, Clone)]
+pub struct UploadStatus {
+    /// Whether each chunk index has been received.
+    pub received: Vec<bool>,
+    /// The SHA-256 digest computed on save for each received chunk.
+    pub digests: Vec<Option<ChunkDigest>>,
+    /// Number of chunks whose content was already present in the dedup store.
+    pub deduplicated_count: usize,
+    /// Bytes skipped because their content was already stored.
+    pub saved_bytes: usize,
+    /// Total bytes across all chunks saved so far, including deduplicated ones.
+    pub total_bytes: usize,
+    /// Raw (uncompressed) size recorded for each chunk index.
+    pub raw_sizes: Vec<usize>,
+    /// On-disk (compressed) size recorded for each chunk index.
+    pub compressed_sizes: Vec<usize>,
+}
+
+impl UploadStatus {
+    /// Creates an empty status for `total_chunks` chunks, all unreceived.
+    fn new(total_chunks: usize) -> Self {
+        Self {
+            received: vec![false; total_chunks],
+            digests: vec![None; total_chunks],
+            deduplicated_count: 0,
+            saved_bytes: 0,
+            total_bytes: 0,
+            raw_sizes: vec![0; total_chunks],
+            compressed_sizes: vec![0; total_chunks],
+        }
+    }
+}
+
+/// Tracks, per `file_id`, which chunks have been received so far.
+pub static UPLOADING_FILES: Lazy<DashMap<String, RwLock<UploadStatus>>> = Lazy::new(DashMap::new);
+
+/// Function signature accepted for naming chunk files on disk.
+///
+/// # Arguments
+///
+/// - `&str` - File identifier.
+/// - `usize` - Chunk index.
+///
+/// # Returns
+///
+/// - `String` - Generated chunk file name.
+pub trait ChunkNaming<'a>: Fn(&'a str, usize) -> String + Send + Sync {}
+
+/// Strategy for saving and merging chunked file uploads.
+///
+/// Generic over the [`ChunkBackend`] used to persist chunk bytes, defaulting
+/// to [`LocalFsBackend`] so existing callers are unaffected.
+pub struct ChunkStrategy<'a, B: ChunkBackend = LocalFsBackend> {
+    pub(crate) upload_dir: &'a str,
+    pub(crate) start_chunk_index: usize,
+    pub(crate) file_id: &'a str,
+    pub(crate) file_name: &'a str,
+    pub(crate) total_chunks: usize,
+    pub(crate) file_name_func: Box<dyn ChunkNaming<'a> + 'static>,
+    pub(crate) backend: B,
+    pub(crate) compression: Compression,
+    pub(crate) max_bytes: Option<u64>,
+}
+
+/// Shared behavior for saving individual chunks and merging them into the final file.
+///
+/// `async fn` is used directly rather than returning a boxed future: nothing in
+/// this crate stores a `dyn HandleStrategy`, so the usual object-safety caveat
+/// around `async fn` in traits doesn't apply here.
+#[allow(async_fn_in_trait)]
+pub trait HandleStrategy<'a> {
+    /// Saves a chunk with index validation and optional checksum verification.
+    async fn save_chunk(
+        &self,
+        chunk_data: &'a [u8],
+        chunk_index: usize,
+        expected: Option<ChunkDigest>,
+    ) -> ChunkStrategyResult;
+
+    /// Merges all chunks into the final file.
+    async fn merge_chunks(&self) -> ChunkStrategyResult;
+}
+
+/// Errors produced while saving or merging chunks.
+#[derive(Debug)]
+pub enum ChunkStrategyError {
+    IndexOutOfBounds(usize, usize),
+    CreateDirectory(String),
+    WriteChunk(String),
+    ReadChunk(String),
+    CreateOutputFile(String),
+    WriteOutput(String),
+    Merge,
+    ChecksumMismatch(usize),
+    UnknownCodec(u8),
+    Compress(String),
+    QuotaExceeded,
+    FileNameTooLong(usize),
+}
+
+impl std::fmt::Display for ChunkStrategyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IndexOutOfBounds(index, total) => {
+                write!(f, "chunk index {} out of bounds for {} total chunks", index, total)
+            }
+            Self::CreateDirectory(msg) => write!(f, "failed to create upload directory: {}", msg),
+            Self::WriteChunk(msg) => write!(f, "failed to write chunk: {}", msg),
+            Self::ReadChunk(msg) => write!(f, "failed to read chunk: {}", msg),
+            Self::CreateOutputFile(msg) => write!(f, "failed to create output file: {}", msg),
+            Self::WriteOutput(msg) => write!(f, "failed to write output file: {}", msg),
+            Self::Merge => write!(f, "not all chunks have been uploaded"),
+            Self::ChecksumMismatch(index) => {
+                write!(f, "chunk {} failed checksum verification", index)
+            }
+            Self::UnknownCodec(tag) => write!(f, "unknown compression codec tag {}", tag),
+            Self::Compress(msg) => write!(f, "compression failure: {}", msg),
+            Self::QuotaExceeded => write!(f, "disk usage quota exceeded"),
+            Self::FileNameTooLong(len) => {
+                write!(f, "generated chunk file name of {} bytes exceeds the limit", len)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ChunkStrategyError {}
+
+/// Result of a chunk save or merge operation.
+pub type ChunkStrategyResult = Result<(), ChunkStrategyError>;
+
+/// Result of constructing a new [`ChunkStrategy`].
+pub type NewChunkStrategyResult<'a, B = LocalFsBackend> = Result<ChunkStrategy<'a, B>, ChunkStrategyError>;
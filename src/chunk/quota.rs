@@ -0,0 +1,127 @@
+use crate::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Maximum length, in bytes, allowed for a generated chunk file name.
+///
+/// Matches the 255-byte file-name limit of common Linux filesystems;
+/// filesystems with shorter limits are not covered by this bound.
+pub const MAX_CHUNK_FILE_NAME_LEN: usize = 255;
+
+/// Process-wide count of bytes currently held across all in-flight chunk uploads.
+pub static USED_SPACE: AtomicU64 = AtomicU64::new(0);
+
+/// Current number of bytes tracked as used by chunk storage.
+///
+/// # Returns
+///
+/// - `u64` - Bytes currently accounted for.
+pub fn used_space() -> u64 {
+    USED_SPACE.load(Ordering::SeqCst)
+}
+
+/// Atomically reserves `bytes` against `max_bytes`, succeeding only if doing so
+/// would not push [`USED_SPACE`] over the ceiling.
+///
+/// Unlike a load-then-add, this is safe under concurrent callers: the
+/// compare-and-swap retries against the latest value, so two callers racing to
+/// reserve the same headroom can't both succeed.
+///
+/// # Arguments
+///
+/// - `u64` - Bytes to reserve.
+/// - `u64` - Quota ceiling.
+///
+/// # Returns
+///
+/// - `bool` - Whether the reservation was granted.
+pub fn try_reserve(bytes: u64, max_bytes: u64) -> bool {
+    let mut current: u64 = USED_SPACE.load(Ordering::SeqCst);
+    loop {
+        if current.saturating_add(bytes) > max_bytes {
+            return false;
+        }
+        match USED_SPACE.compare_exchange_weak(
+            current,
+            current + bytes,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => return true,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// Scans `upload_dir` and initializes [`USED_SPACE`] to the total size of the
+/// chunk data already present, so a restarted process resumes enforcing its
+/// quota against the storage it actually holds rather than starting at zero.
+///
+/// Only descends into the `chunks/` and `cdc/` subdirectories, since those are
+/// the only quota-tracked content `ChunkStrategy` writes: a flat scan of
+/// `upload_dir` would also pick up `.resume` sidecars and finished merge
+/// output, which were never reserved against, and would still miss `cdc/`
+/// segments entirely.
+///
+/// # Arguments
+///
+/// - `&str` - Directory to scan.
+///
+/// # Returns
+///
+/// - `std::io::Result<u64>` - The total bytes found and recorded.
+pub fn init_used_space(upload_dir: &str) -> std::io::Result<u64> {
+    let mut total: u64 = 0;
+    for subdir in ["chunks", "cdc"] {
+        let dir: std::path::PathBuf = Path::new(upload_dir).join(subdir);
+        if dir.exists() {
+            for entry in fs::read_dir(&dir)? {
+                let entry: std::fs::DirEntry = entry?;
+                if entry.file_type()?.is_file() {
+                    total += entry.metadata()?.len();
+                }
+            }
+        }
+    }
+    USED_SPACE.store(total, Ordering::SeqCst);
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_reserve_blocks_once_ceiling_is_reached() {
+        let before: u64 = used_space();
+        let ceiling: u64 = before + 10;
+        assert!(try_reserve(10, ceiling));
+        assert!(!try_reserve(1, ceiling));
+        USED_SPACE.fetch_sub(10, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn init_used_space_counts_only_chunks_and_cdc_subdirs() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let nanos: u128 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir: std::path::PathBuf =
+            std::env::temp_dir().join(format!("chunkify-quota-test-{}", nanos));
+        let chunks_dir: std::path::PathBuf = dir.join("chunks");
+        let cdc_dir: std::path::PathBuf = dir.join("cdc");
+        fs::create_dir_all(&chunks_dir).unwrap();
+        fs::create_dir_all(&cdc_dir).unwrap();
+        fs::write(chunks_dir.join("file.part0"), b"abcde").unwrap();
+        fs::write(cdc_dir.join("deadbeef"), b"abc").unwrap();
+        // These sit directly under `dir`, outside both tracked subdirs, and
+        // must not be counted: a `.resume` sidecar and a finished merge output.
+        fs::write(dir.join("some-file.resume"), b"0\n1\n").unwrap();
+        fs::write(dir.join("out.bin"), b"merged output contents").unwrap();
+
+        let before: u64 = used_space();
+        let total: u64 = init_used_space(dir.to_string_lossy().as_ref()).unwrap();
+        assert_eq!(total, 8);
+        USED_SPACE.store(before, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,8 @@
+/// In-memory upload status rebuilt from a durable sidecar index after a restart.
+#[derive(Debug, Clone)]
+pub struct ResumeState {
+    /// Total chunks expected for this upload.
+    pub total_chunks: usize,
+    /// Whether each chunk index is still present and accounted for.
+    pub received: Vec<bool>,
+}
@@ -0,0 +1,33 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Writes bytes to a file asynchronously, creating or truncating it first.
+///
+/// # Arguments
+///
+/// - `&str` - Path to write.
+/// - `&[u8]` - Bytes to write.
+///
+/// # Returns
+///
+/// - `std::io::Result<()>` - Result of the write.
+pub async fn async_write_to_file(path: &str, data: &[u8]) -> std::io::Result<()> {
+    let mut file: tokio::fs::File = tokio::fs::File::create(path).await?;
+    file.write_all(data).await?;
+    Ok(())
+}
+
+/// Reads the full contents of a file asynchronously.
+///
+/// # Arguments
+///
+/// - `&str` - Path to read.
+///
+/// # Returns
+///
+/// - `std::io::Result<Vec<u8>>` - The file's bytes.
+pub async fn async_read_from_file(path: &str) -> std::io::Result<Vec<u8>> {
+    let mut file: tokio::fs::File = tokio::fs::File::open(path).await?;
+    let mut buf: Vec<u8> = Vec::new();
+    file.read_to_end(&mut buf).await?;
+    Ok(buf)
+}
@@ -0,0 +1,18 @@
+//! Chunkify: chunked file upload handling with save/merge strategies.
+//!
+//! Callers feed byte ranges of a large upload to [`ChunkStrategy`] as they
+//! arrive, and ask it to merge them back into the original file once every
+//! chunk has been reported.
+
+pub use dashmap::{mapref::one::RefMut, DashMap};
+pub use once_cell::sync::Lazy;
+pub use std::fs::{self, File, OpenOptions};
+pub use std::io::{BufWriter, Write};
+pub use std::path::Path;
+pub use tokio::sync::{RwLock, RwLockWriteGuard};
+
+mod chunk;
+mod io;
+
+pub use chunk::*;
+pub use io::*;